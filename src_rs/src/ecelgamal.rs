@@ -1,10 +1,21 @@
 use std::convert::{TryFrom, TryInto};
+use std::io::{Read, Write};
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
 use rand_core::OsRng;
+use sha2::{Digest, Sha256, Sha512};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 use curve25519_dalek::scalar::Scalar;
 use curve25519_dalek::edwards::EdwardsPoint;
 use curve25519_dalek::edwards::CompressedEdwardsY;
 use curve25519_dalek::edwards::EdwardsBasepointTable;
 use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::traits::Identity;
+#[cfg(feature = "serde")]
+use serde::de::Error as _;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// The byte length of a scalar.
 const SCALAR_SIZE: usize = 32;
@@ -31,6 +42,61 @@ fn format_as_hex(f: &mut std::fmt::Formatter<'_>, bytes: &[u8]) -> std::fmt::Res
     Ok(())
 }
 
+/// Parse the lowercase hex strings produced by `format_as_hex` back into `N` raw bytes.
+fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], ()> {
+    if !s.is_ascii() || s.len() != 2 * N {
+        return Err(());
+    }
+    let mut buf = [0u8; N];
+    for i in 0..N {
+        buf[i] = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| ())?;
+    }
+    Ok(buf)
+}
+
+// serde's derived array support only covers lengths up to 32, which is too small for
+// `CIPHER_SIZE`. Serialize/deserialize fixed-size byte arrays of any length as a tuple instead,
+// the same approach the secp256k1 crate uses for its own oversized byte arrays.
+#[cfg(feature = "serde")]
+fn serialize_byte_array<S: Serializer, const N: usize>(
+    bytes: &[u8; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeTuple;
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for byte in bytes {
+        tuple.serialize_element(byte)?;
+    }
+    tuple.end()
+}
+
+#[cfg(feature = "serde")]
+struct ByteArrayVisitor<const N: usize>;
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::de::Visitor<'de> for ByteArrayVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{} raw bytes", N)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut buf = [0u8; N];
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+        }
+        Ok(buf)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_byte_array<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    deserializer.deserialize_tuple(N, ByteArrayVisitor::<N>)
+}
+
 /// Get a random Scalar.
 pub fn random_scalar() -> Scalar {
     let mut csprng = OsRng;
@@ -38,7 +104,7 @@ pub fn random_scalar() -> Scalar {
 }
 
 /// Ciphertext.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Cipher {
     c1: CompressedEdwardsY,
     c2: CompressedEdwardsY,
@@ -59,6 +125,224 @@ impl PartialEq for Cipher {
     }
 }
 
+impl From<&Cipher> for [u8; CIPHER_SIZE] {
+    fn from(cipher: &Cipher) -> Self {
+        let mut buf = [0u8; CIPHER_SIZE];
+        buf[0..POINT_SIZE].copy_from_slice(cipher.c1.as_bytes());
+        buf[POINT_SIZE..CIPHER_SIZE].copy_from_slice(cipher.c2.as_bytes());
+        buf
+    }
+}
+
+impl std::fmt::Display for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let buf: [u8; CIPHER_SIZE] = self.into();
+        format_as_hex(f, &buf)
+    }
+}
+
+/// A `Cipher` may hold arbitrary untrusted bytes (e.g. from `From<[u8; CIPHER_SIZE]>` or
+/// `serde`), so the homomorphic operators below return `None` rather than panicking when a
+/// point fails to decompress.
+impl Add for Cipher {
+    type Output = Option<Cipher>;
+    fn add(self, rhs: Cipher) -> Option<Cipher> {
+        Some(Self {
+            c1: (self.c1.decompress()? + rhs.c1.decompress()?).compress(),
+            c2: (self.c2.decompress()? + rhs.c2.decompress()?).compress(),
+        })
+    }
+}
+
+impl Sub for Cipher {
+    type Output = Option<Cipher>;
+    fn sub(self, rhs: Cipher) -> Option<Cipher> {
+        Some(Self {
+            c1: (self.c1.decompress()? - rhs.c1.decompress()?).compress(),
+            c2: (self.c2.decompress()? - rhs.c2.decompress()?).compress(),
+        })
+    }
+}
+
+impl Mul<Cipher> for Scalar {
+    type Output = Option<Cipher>;
+    fn mul(self, rhs: Cipher) -> Option<Cipher> {
+        Some(Cipher {
+            c1: (self * rhs.c1.decompress()?).compress(),
+            c2: (self * rhs.c2.decompress()?).compress(),
+        })
+    }
+}
+
+impl Cipher {
+    /// Add a fresh encryption of zero to refresh the ciphertext's randomness without
+    /// changing the plaintext it decrypts to. Returns `None` if `self` holds an invalid point.
+    pub fn rerandomize(&self, enc_ctx: &EncryptionContext, pubkey: &PublicKey, r: Option<&Scalar>) -> Option<Cipher> {
+        *self + pubkey.encrypt(enc_ctx, &Scalar::zero(), r)
+    }
+}
+
+impl FromStr for Cipher {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parse_hex::<CIPHER_SIZE>(s)?.into())
+    }
+}
+
+impl TryFrom<&str> for Cipher {
+    type Error = ();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Cipher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            let buf: [u8; CIPHER_SIZE] = self.into();
+            serialize_byte_array(&buf, serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Cipher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(|_| D::Error::custom("invalid Cipher hex string"))
+        } else {
+            let buf = deserialize_byte_array::<_, CIPHER_SIZE>(deserializer)?;
+            Ok(buf.into())
+        }
+    }
+}
+
+/// One entry of the mG discrete-log table: a compressed point `m*G` paired with `m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MGEntry {
+    point: [u8; POINT_SIZE],
+    scalar: u32,
+}
+
+impl PartialOrd for MGEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MGEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.point.cmp(&other.point)
+    }
+}
+
+/// Read the first 4 bytes of a compressed point as a big-endian integer, for interpolation.
+fn load_u32(bytes: &[u8]) -> u32 {
+    ((bytes[0] as u32) << 24) | ((bytes[1] as u32) << 16) | ((bytes[2] as u32) << 8) | (bytes[3] as u32)
+}
+
+/// A table of `{m*G: m}` for `m` in `0..mmax`, sorted by point, used to recover `m` after decryption.
+pub struct MGTable {
+    entries: Vec<MGEntry>,
+}
+
+impl MGTable {
+    /// Generate a table covering `m` in `0..mmax` by iterated point addition, then sort it.
+    ///
+    /// `progress`, if given, is called with the number of points computed so far after each one.
+    pub fn generate<F: FnMut(usize)>(mmax: usize, progress: Option<F>) -> Self {
+        let mut progress = progress;
+        let mut entries = Vec::with_capacity(mmax);
+        let mut point = EdwardsPoint::identity();
+        for m in 0..mmax {
+            entries.push(MGEntry { point: point.compress().to_bytes(), scalar: m as u32 });
+            if let Some(cb) = progress.as_mut() {
+                cb(m + 1);
+            }
+            point += ED25519_BASEPOINT_POINT;
+        }
+        entries.sort_unstable();
+        Self { entries }
+    }
+
+    /// Load a table previously written by [`MGTable::save`] (the `mG.bin` format).
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut entries = Vec::new();
+        loop {
+            let mut buf = [0u8; POINT_SIZE + 4];
+            match reader.read_exact(&mut buf) {
+                Ok(()) => {},
+                Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+            let mut point = [0u8; POINT_SIZE];
+            point.copy_from_slice(&buf[0..POINT_SIZE]);
+            let scalar = u32::from_le_bytes(buf[POINT_SIZE..POINT_SIZE + 4].try_into().unwrap());
+            entries.push(MGEntry { point, scalar });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Write the table to `path` in the `mG.bin` format understood by [`MGTable::load`].
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for entry in &self.entries {
+            writer.write_all(&entry.point)?;
+            writer.write_all(&entry.scalar.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Find `m` such that `table[m] == *target`, by interpolation search over the sorted entries.
+    fn interpolation_search(&self, target: &[u8; POINT_SIZE]) -> Option<u32> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let mut lo = 0usize;
+        let mut hi = self.entries.len() - 1;
+        let mut lo_key = load_u32(&self.entries[lo].point);
+        let mut hi_key = load_u32(&self.entries[hi].point);
+        let key = load_u32(target);
+        loop {
+            if lo > hi || lo_key >= hi_key || key < lo_key || key > hi_key {
+                return if lo <= hi && self.entries[lo].point == *target { Some(self.entries[lo].scalar) } else { None };
+            }
+            let mid = lo + (((key - lo_key) as usize) * (hi - lo)) / ((hi_key - lo_key) as usize);
+            match self.entries[mid].point.cmp(target) {
+                std::cmp::Ordering::Equal => return Some(self.entries[mid].scalar),
+                std::cmp::Ordering::Less => {
+                    if mid + 1 > hi {
+                        return None;
+                    }
+                    lo = mid + 1;
+                    lo_key = load_u32(&self.entries[lo].point);
+                },
+                std::cmp::Ordering::Greater => {
+                    if mid == 0 {
+                        return None;
+                    }
+                    hi = mid - 1;
+                    hi_key = load_u32(&self.entries[hi].point);
+                },
+            }
+        }
+    }
+
+    /// Decrypt `cipher` with `privkey`, returning the plaintext `m`, or `None` if `privkey` is
+    /// wrong or the encrypted message is not less than `self`'s `mmax`.
+    pub fn decrypt(&self, privkey: &PrivateKey, cipher: &Cipher) -> Option<u32> {
+        let c1 = cipher.c1.decompress()?;
+        let c2 = cipher.c2.decompress()?;
+        let m = (c2 - privkey.scalar * c1).compress();
+        self.interpolation_search(&m.to_bytes())
+    }
+}
+
 /// A context need to encrypt a message.
 pub struct EncryptionContext {
     table: EdwardsBasepointTable,
@@ -77,11 +361,18 @@ pub trait Encrypt {
 }
 
 /// A private key.
-#[derive(Debug)]
 pub struct PrivateKey {
     scalar: Scalar,
 }
 
+impl std::fmt::Debug for PrivateKey {
+    /// Deliberately does not print `self.scalar`: `Scalar`'s own `Debug` impl dumps the raw
+    /// secret bytes, which would defeat the zeroing and constant-time comparison below.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
 impl PrivateKey {
     pub fn new() -> Self {
         Self {
@@ -112,8 +403,9 @@ impl Encrypt for PrivateKey {
 }
 
 impl PartialEq for PrivateKey {
+    /// Compares the secret scalar in constant time, to avoid leaking it through timing.
     fn eq(&self, other: &Self) -> bool {
-        self.scalar == other.scalar
+        self.scalar.ct_eq(&other.scalar).into()
     }
 }
 
@@ -124,6 +416,56 @@ impl std::fmt::Display for PrivateKey {
     }
 }
 
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl FromStr for PrivateKey {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parse_hex::<SCALAR_SIZE>(s)?.into())
+    }
+}
+
+impl TryFrom<&str> for PrivateKey {
+    type Error = ();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PrivateKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serialize_byte_array(&self.scalar.to_bytes(), serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PrivateKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(|_| D::Error::custom("invalid PrivateKey hex string"))
+        } else {
+            let buf = deserialize_byte_array::<_, SCALAR_SIZE>(deserializer)?;
+            Ok(buf.into())
+        }
+    }
+}
+
 /// A public key.
 #[derive(Debug)]
 pub struct PublicKey {
@@ -176,9 +518,149 @@ impl std::fmt::Display for PublicKey {
     }
 }
 
+impl FromStr for PublicKey {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_hex::<POINT_SIZE>(s)?.try_into()
+    }
+}
+
+impl TryFrom<&str> for PublicKey {
+    type Error = ();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serialize_byte_array(&self.point.compress().to_bytes(), serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(|_| D::Error::custom("invalid PublicKey hex string"))
+        } else {
+            let buf = deserialize_byte_array::<_, POINT_SIZE>(deserializer)?;
+            buf.try_into().map_err(|_| D::Error::custom("invalid PublicKey point"))
+        }
+    }
+}
+
+/// The byte length of the integrity tag appended to a hybrid-encrypted message.
+const TAG_SIZE: usize = 32;
+
+/// Errors returned when decoding or decrypting a hybrid-encrypted message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HybridDecryptError {
+    /// The ciphertext is shorter than a `C1` point plus an integrity tag.
+    Truncated,
+    /// `C1` is not a valid compressed Edwards point.
+    InvalidPoint,
+    /// The recovered plaintext does not match the integrity tag.
+    TagMismatch,
+}
+
+impl std::fmt::Display for HybridDecryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "ciphertext is too short to contain C1 and a tag"),
+            Self::InvalidPoint => write!(f, "C1 is not a valid curve point"),
+            Self::TagMismatch => write!(f, "integrity tag does not match the plaintext"),
+        }
+    }
+}
+
+impl std::error::Error for HybridDecryptError {}
+
+/// Derive a keystream of `len` bytes from the compressed shared point `shared`, as
+/// `SHA-512(shared || counter)` blocks concatenated in counter order.
+fn hybrid_kdf(shared: &[u8; POINT_SIZE], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + Sha512::output_size());
+    let mut counter: u32 = 1;
+    while out.len() < len {
+        let mut hasher = Sha512::new();
+        hasher.update(shared);
+        hasher.update(counter.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// Compute the integrity tag `SHA-256(shared || plaintext)` binding a hybrid ciphertext's body
+/// to the shared point it was encrypted under.
+fn hybrid_tag(shared: &[u8; POINT_SIZE], plaintext: &[u8]) -> [u8; TAG_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update(shared);
+    hasher.update(plaintext);
+    hasher.finalize().into()
+}
+
+fn xor_with_keystream(data: &[u8], keystream: &[u8]) -> Vec<u8> {
+    data.iter().zip(keystream).map(|(d, k)| d ^ k).collect()
+}
+
+impl PublicKey {
+    /// Hybrid SM2-PKE-style encryption of an arbitrary-length byte message, for payloads too
+    /// large for the scalar-only [`Encrypt`] path used by PIR. Samples an ephemeral `r`, derives
+    /// a keystream and integrity tag from the shared point `r * self`, and returns the
+    /// self-describing form `C1 || tag || body`.
+    pub fn encrypt_bytes(&self, enc_ctx: &EncryptionContext, msg: &[u8], r: Option<&Scalar>) -> Vec<u8> {
+        let rr = match r {
+            Some(r) => *r,
+            None => random_scalar(),
+        };
+        let c1 = enc_ctx.table.basepoint_mul(&rr).compress();
+        let shared = (rr * self.point).compress().to_bytes();
+        let tag = hybrid_tag(&shared, msg);
+        let body = xor_with_keystream(msg, &hybrid_kdf(&shared, msg.len()));
+        let mut out = Vec::with_capacity(POINT_SIZE + TAG_SIZE + body.len());
+        out.extend_from_slice(c1.as_bytes());
+        out.extend_from_slice(&tag);
+        out.extend_from_slice(&body);
+        out
+    }
+}
+
+impl PrivateKey {
+    /// Decrypt a message produced by [`PublicKey::encrypt_bytes`]. Recovers the shared point as
+    /// `self * C1`, regenerates the keystream, and rejects the message if its integrity tag
+    /// doesn't match.
+    pub fn decrypt_bytes(&self, ciphertext: &[u8]) -> Result<Vec<u8>, HybridDecryptError> {
+        if ciphertext.len() < POINT_SIZE + TAG_SIZE {
+            return Err(HybridDecryptError::Truncated);
+        }
+        let c1 = CompressedEdwardsY::from_slice(&ciphertext[0..POINT_SIZE])
+            .decompress()
+            .ok_or(HybridDecryptError::InvalidPoint)?;
+        let tag = &ciphertext[POINT_SIZE..POINT_SIZE + TAG_SIZE];
+        let body = &ciphertext[POINT_SIZE + TAG_SIZE..];
+        let shared = (self.scalar * c1).compress().to_bytes();
+        let plaintext = xor_with_keystream(body, &hybrid_kdf(&shared, body.len()));
+        if !bool::from(hybrid_tag(&shared, &plaintext).ct_eq(tag)) {
+            return Err(HybridDecryptError::TagMismatch);
+        }
+        Ok(plaintext)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn sha256(buf: &[u8]) -> [u8; 32] {
+        Sha256::digest(buf).into()
+    }
     const PRIVKEY: [u8; SCALAR_SIZE] = [
         0x7e, 0xf6, 0xad, 0xd2, 0xbe, 0xd5, 0x9a, 0x79,
         0xba, 0x6e, 0xdc, 0xfb, 0xa4, 0x8f, 0xde, 0x7a,
@@ -222,6 +704,21 @@ mod tests {
         0xf8, 0x89, 0x40, 0x35, 0xe0, 0x30, 0xd2, 0x13,
         0x50, 0x80, 0x84, 0x31, 0xb8, 0x00, 0x8a, 0xf2
     ];
+
+    /// A shared `EncryptionContext`, built once and reused by tests that don't care about its
+    /// construction, to avoid rebuilding the basepoint table in every test.
+    fn shared_enc_ctx() -> &'static EncryptionContext {
+        static CTX: std::sync::OnceLock<EncryptionContext> = std::sync::OnceLock::new();
+        CTX.get_or_init(EncryptionContext::new)
+    }
+
+    /// A shared small-scale `MGTable`, built once and reused by tests that don't care about its
+    /// generation, to avoid regenerating and sorting `SMALL_MMAX` entries in every test.
+    fn small_mg_table() -> &'static MGTable {
+        static TABLE: std::sync::OnceLock<MGTable> = std::sync::OnceLock::new();
+        TABLE.get_or_init(|| MGTable::generate(SMALL_MMAX, None::<fn(usize)>))
+    }
+
     #[test]
     fn create_private_key() {
         PrivateKey::new();
@@ -232,6 +729,54 @@ mod tests {
         assert_eq!(pubkey, PUBKEY.try_into().unwrap());
     }
     #[test]
+    fn private_key_eq() {
+        let a = PrivateKey::from(PRIVKEY);
+        let b = PrivateKey::from(PRIVKEY);
+        assert_eq!(a, b);
+        assert_ne!(a, PrivateKey::new());
+    }
+    #[test]
+    fn private_key_debug_does_not_leak_scalar() {
+        let privkey = PrivateKey::from(PRIVKEY);
+        let debug = format!("{:?}", privkey);
+        assert!(!debug.contains(&privkey.to_string()));
+        assert_eq!(debug, "PrivateKey { .. }");
+    }
+    #[test]
+    fn private_key_from_str_roundtrip() {
+        let privkey = PrivateKey::from(PRIVKEY);
+        let parsed: PrivateKey = privkey.to_string().parse().unwrap();
+        assert_eq!(privkey, parsed);
+    }
+    #[test]
+    fn private_key_from_str_rejects_garbage() {
+        assert!(PrivateKey::from_str("not hex").is_err());
+        assert!(PrivateKey::from_str("00").is_err());
+    }
+    #[test]
+    fn private_key_from_str_rejects_non_ascii_without_panicking() {
+        let s = format!("a\u{e9}{}a", "ab".repeat(30));
+        assert_eq!(s.len(), 2 * SCALAR_SIZE);
+        assert!(PrivateKey::from_str(&s).is_err());
+    }
+    #[test]
+    fn public_key_from_str_roundtrip() {
+        let pubkey: PublicKey = PUBKEY.try_into().unwrap();
+        let parsed: PublicKey = pubkey.to_string().parse().unwrap();
+        assert_eq!(pubkey, parsed);
+    }
+    #[test]
+    fn public_key_from_str_rejects_garbage() {
+        assert!(PublicKey::from_str("not hex").is_err());
+        assert!(PublicKey::from_str("00").is_err());
+    }
+    #[test]
+    fn public_key_from_str_rejects_non_ascii_without_panicking() {
+        let s = format!("a\u{e9}{}a", "ab".repeat(30));
+        assert_eq!(s.len(), 2 * POINT_SIZE);
+        assert!(PublicKey::from_str(&s).is_err());
+    }
+    #[test]
     fn encrypt_normal() {
         let enc_ctx = EncryptionContext::new();
         let pubkey = PublicKey::new(&PRIVKEY.into());
@@ -245,72 +790,219 @@ mod tests {
         let cipher = privkey.encrypt(&enc_ctx, &MSG.into(), Some(&Scalar::from_bits(R)));
         assert_eq!(cipher, CIPHER.into());
     }
-/*
-TEST(ECElGamalTest, mG_generate_no_sort) {
-	size_t points_computed = 0;
-	epir_mG_generate_no_sort(mG_test.data(), mG_test.size(), [](const size_t points_computed_test, void *data) {
-		size_t *points_computed = (size_t*)data;
-		(*points_computed)++;
-		EXPECT_EQ(points_computed_test, *points_computed);
-	}, &points_computed);
-}
-TEST(ECElGamalTest, mG_generate_sort) {
-	epir_mG_sort(mG_test.data(), mG_test.size());
-	ASSERT_PRED2(SameHash<epir_mG_t>, mG_test, mG_hash_small);
-}
-TEST(ECElGamalTest, mG_generate) {
-	epir_mG_generate(mG_test.data(), mG_test.size(), NULL, NULL);
-	ASSERT_PRED2(SameHash<epir_mG_t>, mG_test, mG_hash_small);
-}
-TEST(ECElGamalTest, mG_interpolation_search) {
-	#pragma omp parallel for
-	for(size_t i=0; i<mG_test.size(); i++) {
-		epir_mG_t mG = mG_test[i];
-		const int32_t scalar_test = epir_mG_interpolation_search(mG.point, mG_test.data(), mG_test.size());
-		EXPECT_EQ(scalar_test, (int32_t)mG.scalar);
-	}
-}
-*/
+    #[test]
+    fn mg_generate() {
+        let mut points_computed = 0;
+        let table = MGTable::generate(SMALL_MMAX, Some(|pc: usize| {
+            points_computed += 1;
+            assert_eq!(pc, points_computed);
+        }));
+        assert_eq!(points_computed, SMALL_MMAX);
+        let mut buf = Vec::with_capacity(table.entries.len() * (POINT_SIZE + 4));
+        for entry in &table.entries {
+            buf.extend_from_slice(&entry.point);
+            buf.extend_from_slice(&entry.scalar.to_le_bytes());
+        }
+        assert_eq!(sha256(&buf), MG_HASH_SMALL);
+    }
+    #[test]
+    fn mg_interpolation_search() {
+        let table = MGTable::generate(SMALL_MMAX, None::<fn(usize)>);
+        for entry in &table.entries {
+            assert_eq!(table.interpolation_search(&entry.point), Some(entry.scalar));
+        }
+    }
     #[test]
     fn mg_default_path() {
         assert_eq!(super::mg_default_path().unwrap(), std::env::var("HOME").unwrap() + "/.EllipticPIR/mG.bin");
     }
-/*
-TEST(ECElGamalTest, mG_load_default) {
-	// Write mG.bin to /tmp/mG.bin.
-	const std::string path = "/tmp/mG.bin";
-	std::ofstream ofs(std::string(path), std::ios::binary | std::ios::out);
-	ASSERT_FALSE(ofs.fail());
-	ofs.write((const char*)mG_test.data(), sizeof(epir_mG_t) * mG_test.size());
-	ofs.close();
-	// Load.
-	static std::vector<epir_mG_t> mG_test2(mG_test.size());
-	const size_t elems_read = epir_mG_load(mG_test2.data(), mG_test.size(), path.c_str());
-	EXPECT_EQ(elems_read, mG_test.size());
-	EXPECT_PRED2(SameHash<epir_mG_t>, mG_test2, mG_hash_small);
-	// Delete.
-	EXPECT_TRUE(std::filesystem::remove(path));
-}
-TEST(ECElGamalTest, decrypt_success) {
-	const int32_t decrypted = epir_ecelgamal_decrypt(privkey, cipher, mG.data(), EPIR_DEFAULT_MG_MAX);
-	ASSERT_EQ(decrypted, (int32_t)msg);
-}
-TEST(ECElGamalTest, decrypt_fail) {
-	const int32_t decrypted = epir_ecelgamal_decrypt(pubkey, cipher, mG.data(), EPIR_DEFAULT_MG_MAX);
-	ASSERT_EQ(decrypted, -1);
-}
-TEST(ECElGamalTest, random_encrypt_normal) {
-	unsigned char cipher_test[EPIR_CIPHER_SIZE];
-	epir_ecelgamal_encrypt(cipher_test, pubkey, msg, NULL);
-	const int32_t decrypted = epir_ecelgamal_decrypt(privkey, cipher, mG.data(), EPIR_DEFAULT_MG_MAX);
-	ASSERT_EQ(decrypted, (int32_t)msg);
-}
-TEST(ECElGamalTest, random_encrypt_fast) {
-	unsigned char cipher_test[EPIR_CIPHER_SIZE];
-	epir_ecelgamal_encrypt_fast(cipher_test, privkey, msg, NULL);
-	const int32_t decrypted = epir_ecelgamal_decrypt(privkey, cipher, mG.data(), EPIR_DEFAULT_MG_MAX);
-	ASSERT_EQ(decrypted, (int32_t)msg);
-}
-#endif
-*/
+    #[test]
+    fn mg_load_save() {
+        let table = MGTable::generate(SMALL_MMAX, None::<fn(usize)>);
+        let path = std::env::temp_dir().join("ci-lib-ecelgamal-test-mG.bin");
+        let path = path.to_str().unwrap();
+        table.save(path).unwrap();
+        let loaded = MGTable::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(table.entries, loaded.entries);
+    }
+    #[test]
+    fn decrypt_success() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let msg: u32 = 1234;
+        let cipher = pubkey.encrypt(enc_ctx, &Scalar::from(msg), None);
+        assert_eq!(table.decrypt(&privkey, &cipher), Some(msg));
+    }
+    #[test]
+    fn decrypt_fail() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let cipher = pubkey.encrypt(enc_ctx, &Scalar::from(1234u32), None);
+        assert_eq!(table.decrypt(&PrivateKey::new(), &cipher), None);
+    }
+    #[test]
+    fn homomorphic_add() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let (m1, m2): (u32, u32) = (123, 456);
+        let c1 = pubkey.encrypt(enc_ctx, &Scalar::from(m1), None);
+        let c2 = pubkey.encrypt(enc_ctx, &Scalar::from(m2), None);
+        assert_eq!(table.decrypt(&privkey, &(c1 + c2).unwrap()), Some(m1 + m2));
+    }
+    #[test]
+    fn homomorphic_sub() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let (m1, m2): (u32, u32) = (456, 123);
+        let c1 = pubkey.encrypt(enc_ctx, &Scalar::from(m1), None);
+        let c2 = pubkey.encrypt(enc_ctx, &Scalar::from(m2), None);
+        assert_eq!(table.decrypt(&privkey, &(c1 - c2).unwrap()), Some(m1 - m2));
+    }
+    #[test]
+    fn homomorphic_scalar_mul() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let m: u32 = 123;
+        let k: u32 = 7;
+        let cipher = pubkey.encrypt(enc_ctx, &Scalar::from(m), None);
+        assert_eq!(table.decrypt(&privkey, &(Scalar::from(k) * cipher).unwrap()), Some(m * k));
+    }
+    #[test]
+    fn rerandomize_preserves_plaintext() {
+        let enc_ctx = shared_enc_ctx();
+        let table = small_mg_table();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let msg: u32 = 789;
+        let cipher = pubkey.encrypt(enc_ctx, &Scalar::from(msg), None);
+        let rerandomized = cipher.rerandomize(enc_ctx, &pubkey, None).unwrap();
+        assert_ne!(cipher, rerandomized);
+        assert_eq!(table.decrypt(&privkey, &rerandomized), Some(msg));
+    }
+    #[test]
+    fn homomorphic_add_rejects_invalid_point() {
+        let enc_ctx = shared_enc_ctx();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let cipher = pubkey.encrypt(enc_ctx, &Scalar::from(123u32), None);
+        let mut invalid = [0u8; CIPHER_SIZE];
+        invalid[POINT_SIZE - 1] = 0xff;
+        let invalid: Cipher = invalid.into();
+        assert_eq!(cipher + invalid, None);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_private_key_json_roundtrip() {
+        let privkey = PrivateKey::from(PRIVKEY);
+        let json = serde_json::to_string(&privkey).unwrap();
+        assert_eq!(json, format!("\"{}\"", privkey));
+        let parsed: PrivateKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(privkey, parsed);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_private_key_bincode_roundtrip() {
+        let privkey = PrivateKey::from(PRIVKEY);
+        let bytes = bincode::serialize(&privkey).unwrap();
+        assert_eq!(bytes, PRIVKEY.to_vec());
+        let parsed: PrivateKey = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(privkey, parsed);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_public_key_json_roundtrip() {
+        let pubkey: PublicKey = PUBKEY.try_into().unwrap();
+        let json = serde_json::to_string(&pubkey).unwrap();
+        assert_eq!(json, format!("\"{}\"", pubkey));
+        let parsed: PublicKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(pubkey, parsed);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_public_key_bincode_rejects_invalid_point() {
+        let mut buf = [0u8; POINT_SIZE];
+        buf[POINT_SIZE - 1] = 0xff;
+        let bytes = bincode::serialize(&buf).unwrap();
+        assert!(bincode::deserialize::<PublicKey>(&bytes).is_err());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_cipher_json_roundtrip() {
+        let cipher: Cipher = CIPHER.into();
+        let json = serde_json::to_string(&cipher).unwrap();
+        assert_eq!(json, format!("\"{}\"", cipher));
+        let parsed: Cipher = serde_json::from_str(&json).unwrap();
+        assert_eq!(cipher, parsed);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_cipher_bincode_roundtrip() {
+        let cipher: Cipher = CIPHER.into();
+        let bytes = bincode::serialize(&cipher).unwrap();
+        assert_eq!(bytes, CIPHER.to_vec());
+        let parsed: Cipher = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(cipher, parsed);
+    }
+    #[test]
+    fn hybrid_encrypt_decrypt_roundtrip() {
+        let enc_ctx = shared_enc_ctx();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let msg = b"the quick brown fox jumps over the lazy dog";
+        let ciphertext = pubkey.encrypt_bytes(enc_ctx, msg, None);
+        assert_eq!(privkey.decrypt_bytes(&ciphertext).unwrap(), msg);
+    }
+    #[test]
+    fn hybrid_encrypt_decrypt_empty_message() {
+        let enc_ctx = shared_enc_ctx();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let ciphertext = pubkey.encrypt_bytes(enc_ctx, b"", None);
+        assert_eq!(privkey.decrypt_bytes(&ciphertext).unwrap(), b"");
+    }
+    #[test]
+    fn hybrid_decrypt_wrong_key_fails() {
+        let enc_ctx = shared_enc_ctx();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let ciphertext = pubkey.encrypt_bytes(enc_ctx, b"top secret", None);
+        let err = PrivateKey::new().decrypt_bytes(&ciphertext).unwrap_err();
+        assert_eq!(err, HybridDecryptError::TagMismatch);
+    }
+    #[test]
+    fn hybrid_decrypt_tampered_body_fails() {
+        let enc_ctx = shared_enc_ctx();
+        let privkey = PrivateKey::new();
+        let pubkey = PublicKey::new(&privkey);
+        let mut ciphertext = pubkey.encrypt_bytes(enc_ctx, b"top secret", None);
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 0xff;
+        let err = privkey.decrypt_bytes(&ciphertext).unwrap_err();
+        assert_eq!(err, HybridDecryptError::TagMismatch);
+    }
+    #[test]
+    fn hybrid_decrypt_truncated_fails() {
+        let privkey = PrivateKey::new();
+        let err = privkey.decrypt_bytes(&[0u8; POINT_SIZE]).unwrap_err();
+        assert_eq!(err, HybridDecryptError::Truncated);
+    }
+    #[test]
+    fn hybrid_decrypt_invalid_point_fails() {
+        let privkey = PrivateKey::new();
+        let mut ciphertext = vec![0u8; POINT_SIZE + TAG_SIZE];
+        ciphertext[POINT_SIZE - 1] = 0xff;
+        let err = privkey.decrypt_bytes(&ciphertext).unwrap_err();
+        assert_eq!(err, HybridDecryptError::InvalidPoint);
+    }
 }